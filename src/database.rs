@@ -0,0 +1,108 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+/// A record of a single package that alloy has installed onto the system.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackageRecord {
+    /// Package name.
+    pub name: String,
+    /// Version that is currently installed.
+    pub version: String,
+    /// Unix timestamp (seconds) of when the package was installed.
+    pub installed_at: u64,
+    /// `true` if the user asked for this package explicitly, `false` if it was
+    /// pulled in to satisfy another package's dependency.
+    pub explicit: bool,
+    /// Absolute paths of every file alloy placed on the system for this package.
+    pub files: Vec<String>,
+}
+
+impl PackageRecord {
+    /// Returns `true` if this package was installed only as a dependency.
+    pub fn is_dependency(&self) -> bool {
+        !self.explicit
+    }
+}
+
+/// The on-disk store of installed packages.
+///
+/// All four of `list`, `remove`, `info`, and `install` read and write through a
+/// single `Database` so they agree on what is actually installed rather than
+/// each guessing independently.
+pub struct Database {
+    path: PathBuf,
+    packages: BTreeMap<String, PackageRecord>,
+}
+
+impl Database {
+    /// Loads the database from its default location, returning an empty
+    /// database if the file does not exist yet.
+    pub fn load() -> io::Result<Self> {
+        let path = Self::default_path();
+        Self::load_from(path)
+    }
+
+    /// Loads the database from an explicit path.
+    pub fn load_from(path: PathBuf) -> io::Result<Self> {
+        let packages = match fs::read(&path) {
+            Ok(bytes) => serde_json::from_slice(&bytes)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(e) => return Err(e),
+        };
+        Ok(Self { path, packages })
+    }
+
+    /// `~/.local/state/alloy/installed.json`, following the XDG state dir.
+    fn default_path() -> PathBuf {
+        let base = std::env::var_os("XDG_STATE_HOME")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| {
+                let mut home = PathBuf::from(std::env::var_os("HOME").unwrap_or_default());
+                home.push(".local/state");
+                home
+            });
+        base.join("alloy").join("installed.json")
+    }
+
+    /// Records a freshly installed (or upgraded) package, overwriting any
+    /// previous record for the same name.
+    pub fn record_install(&mut self, record: &PackageRecord) -> io::Result<()> {
+        self.packages.insert(record.name.clone(), record.clone());
+        self.flush()
+    }
+
+    /// Drops the record for `name`, returning it if it was present.
+    pub fn remove(&mut self, name: &str) -> io::Result<Option<PackageRecord>> {
+        let removed = self.packages.remove(name);
+        if removed.is_some() {
+            self.flush()?;
+        }
+        Ok(removed)
+    }
+
+    /// Looks up a single package without modifying the database.
+    pub fn get(&self, name: &str) -> Option<&PackageRecord> {
+        self.packages.get(name)
+    }
+
+    /// Returns every installed package, sorted by name.
+    pub fn list(&self) -> Vec<PackageRecord> {
+        self.packages.values().cloned().collect()
+    }
+
+    /// Writes the current state back to disk, creating parent directories as
+    /// needed.
+    fn flush(&self) -> io::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let bytes = serde_json::to_vec_pretty(&self.packages)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        fs::write(&self.path, bytes)
+    }
+}
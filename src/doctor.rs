@@ -0,0 +1,297 @@
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::database::Database;
+use crate::repository::Config;
+
+/// Outcome of a single diagnostic check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Ok,
+    Warn,
+    Fail,
+}
+
+impl Status {
+    fn label(self) -> &'static str {
+        match self {
+            Status::Ok => "ok",
+            Status::Warn => "warn",
+            Status::Fail => "fail",
+        }
+    }
+}
+
+/// The report produced by running one [`Check`].
+pub struct CheckResult {
+    pub name: String,
+    pub status: Status,
+    pub detail: String,
+    pub suggested_fix: Option<String>,
+}
+
+impl CheckResult {
+    fn ok(name: &str, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            status: Status::Ok,
+            detail: detail.into(),
+            suggested_fix: None,
+        }
+    }
+
+    fn warn(name: &str, detail: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            status: Status::Warn,
+            detail: detail.into(),
+            suggested_fix: Some(fix.into()),
+        }
+    }
+
+    fn fail(name: &str, detail: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            status: Status::Fail,
+            detail: detail.into(),
+            suggested_fix: Some(fix.into()),
+        }
+    }
+}
+
+/// A single, independent health check.
+pub trait Check {
+    /// Runs the check and reports its result.
+    fn run(&self) -> CheckResult;
+
+    /// Attempts the suggested remediation, returning a description of what was
+    /// done (or would be done under `--dry-run`). The default is a no-op.
+    fn fix(&self, _dry_run: bool) -> io::Result<Option<String>> {
+        Ok(None)
+    }
+}
+
+/// Config file exists and parses.
+struct ConfigCheck;
+
+impl Check for ConfigCheck {
+    fn run(&self) -> CheckResult {
+        let path = Config::default_path();
+        if !path.exists() {
+            return CheckResult::warn(
+                "config",
+                format!("{} does not exist", path.display()),
+                "create the file with at least one [[repository]] entry",
+            );
+        }
+        match Config::load_from(path.clone()) {
+            Ok(_) => CheckResult::ok("config", format!("{} parses", path.display())),
+            Err(e) => CheckResult::fail(
+                "config",
+                format!("{} failed to parse: {}", path.display(), e),
+                "fix the TOML syntax in the config file",
+            ),
+        }
+    }
+}
+
+/// At least one repository is configured. Lists the configured repositories;
+/// whether each one is reachable is not tested.
+struct RepositoriesCheck;
+
+impl Check for RepositoriesCheck {
+    fn run(&self) -> CheckResult {
+        let config = match Config::load() {
+            Ok(config) => config,
+            Err(e) => {
+                return CheckResult::fail(
+                    "repositories",
+                    format!("could not load config: {}", e),
+                    "fix the config file (see the config check)",
+                )
+            }
+        };
+        let repos = config.repositories();
+        if repos.is_empty() {
+            return CheckResult::warn(
+                "repositories",
+                "no repositories configured",
+                "add [[repository]] entries to the config file",
+            );
+        }
+        // TODO: issue a real request to each URL once the HTTP backend lands.
+        let names: Vec<&str> = repos.iter().map(|r| r.name()).collect();
+        CheckResult::ok(
+            "repositories",
+            format!("{} configured: {}", repos.len(), names.join(", ")),
+        )
+    }
+}
+
+/// Install database can be read and is not corrupt. An absent database file
+/// loads as empty and passes.
+struct DatabaseCheck;
+
+impl Check for DatabaseCheck {
+    fn run(&self) -> CheckResult {
+        match Database::load() {
+            Ok(db) => CheckResult::ok(
+                "database",
+                format!("{} package(s) recorded", db.list().len()),
+            ),
+            Err(e) if e.kind() == io::ErrorKind::InvalidData => CheckResult::fail(
+                "database",
+                format!("install database is corrupt: {}", e),
+                "inspect or remove the installed.json state file",
+            ),
+            Err(e) => CheckResult::fail(
+                "database",
+                format!("could not read install database: {}", e),
+                "check permissions on the state directory",
+            ),
+        }
+    }
+}
+
+/// Recorded files for installed packages still exist on disk.
+struct OrphanedFilesCheck;
+
+impl OrphanedFilesCheck {
+    /// Returns, per package, the recorded files that are missing from disk.
+    fn missing(db: &Database) -> Vec<(String, Vec<String>)> {
+        let mut out = Vec::new();
+        for record in db.list() {
+            let gone: Vec<String> = record
+                .files
+                .iter()
+                .filter(|f| !Path::new(f).exists())
+                .cloned()
+                .collect();
+            if !gone.is_empty() {
+                out.push((record.name, gone));
+            }
+        }
+        out
+    }
+}
+
+impl Check for OrphanedFilesCheck {
+    fn run(&self) -> CheckResult {
+        let db = match Database::load() {
+            Ok(db) => db,
+            Err(e) => {
+                return CheckResult::fail(
+                    "orphaned-files",
+                    format!("could not read install database: {}", e),
+                    "see the database check",
+                )
+            }
+        };
+        let missing = Self::missing(&db);
+        if missing.is_empty() {
+            return CheckResult::ok("orphaned-files", "all recorded files present");
+        }
+        let names: Vec<&str> = missing.iter().map(|(n, _)| n.as_str()).collect();
+        CheckResult::warn(
+            "orphaned-files",
+            format!("missing files for: {}", names.join(", ")),
+            "run `alloy doctor --fix` to prune packages whose files are gone",
+        )
+    }
+
+    fn fix(&self, dry_run: bool) -> io::Result<Option<String>> {
+        let mut db = Database::load()?;
+        let missing = Self::missing(&db);
+        if missing.is_empty() {
+            return Ok(None);
+        }
+        let mut pruned = Vec::new();
+        for (name, _) in missing {
+            if !dry_run {
+                db.remove(&name)?;
+            }
+            pruned.push(name);
+        }
+        let verb = if dry_run { "would prune" } else { "pruned" };
+        Ok(Some(format!("{} {}", verb, pruned.join(", "))))
+    }
+}
+
+/// The install prefix is writable.
+struct PrefixCheck;
+
+impl PrefixCheck {
+    fn prefix() -> PathBuf {
+        PathBuf::from("/usr/local")
+    }
+}
+
+impl Check for PrefixCheck {
+    fn run(&self) -> CheckResult {
+        let prefix = Self::prefix();
+        // SAFETY: `access` only inspects the path and never mutates state.
+        let writable = {
+            let Ok(cpath) = std::ffi::CString::new(prefix.as_os_str().to_string_lossy().as_bytes())
+            else {
+                return CheckResult::fail(
+                    "prefix",
+                    format!("invalid prefix path {}", prefix.display()),
+                    "set a valid install prefix",
+                );
+            };
+            unsafe { libc::access(cpath.as_ptr(), libc::W_OK) == 0 }
+        };
+        if writable {
+            CheckResult::ok("prefix", format!("{} is writable", prefix.display()))
+        } else {
+            CheckResult::warn(
+                "prefix",
+                format!("{} is not writable by this user", prefix.display()),
+                "installs will escalate via sudo; this is expected for a system prefix",
+            )
+        }
+    }
+}
+
+/// Every check alloy runs, in report order.
+fn checks() -> Vec<Box<dyn Check>> {
+    vec![
+        Box::new(ConfigCheck),
+        Box::new(RepositoriesCheck),
+        Box::new(DatabaseCheck),
+        Box::new(OrphanedFilesCheck),
+        Box::new(PrefixCheck),
+    ]
+}
+
+/// Runs every diagnostic check, prints a grouped summary, applies fixes when
+/// `fix` is set, and returns a nonzero-worthy exit code if any check failed.
+pub fn run(fix: bool, dry_run: bool) -> i32 {
+    let (mut ok, mut warn, mut fail) = (0u32, 0u32, 0u32);
+    for check in checks() {
+        let result = check.run();
+        match result.status {
+            Status::Ok => ok += 1,
+            Status::Warn => warn += 1,
+            Status::Fail => fail += 1,
+        }
+        println!("[{}] {}: {}", result.status.label(), result.name, result.detail);
+        if let Some(suggestion) = &result.suggested_fix {
+            println!("       fix: {}", suggestion);
+        }
+        if fix && result.status != Status::Ok {
+            match check.fix(dry_run) {
+                Ok(Some(done)) => println!("       -> {}", done),
+                Ok(None) => {}
+                Err(e) => println!("       -> remediation failed: {}", e),
+            }
+        }
+    }
+
+    println!("\n{} ok, {} warn, {} fail", ok, warn, fail);
+    if fail > 0 {
+        1
+    } else {
+        0
+    }
+}
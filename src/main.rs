@@ -1,4 +1,17 @@
-use clap::{Parser, Subcommand};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use clap::{CommandFactory, Parser, Subcommand};
+use clap_complete::{generate, Shell};
+
+use crate::database::{Database, PackageRecord};
+use crate::repository::{Config, PackageMeta};
+
+mod database;
+mod doctor;
+mod privilege;
+mod repository;
+mod resolver;
+mod version;
 
 /// A fast, opinionated package manager that installs software directly onto your system
 #[derive(Parser)]
@@ -9,6 +22,10 @@ struct Cli {
     #[arg(long, global = true)]
     dry_run: bool,
 
+    /// Keep the sudo credential refreshed in the background during long operations
+    #[arg(long, global = true)]
+    sudoloop: bool,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -44,8 +61,47 @@ enum Commands {
         package: String,
     },
 
+    /// Search configured repositories for a package
+    Search {
+        /// Substring to match against package names
+        query: String,
+    },
+
+    /// Upgrade installed packages to their newest available versions
+    Upgrade {
+        /// Packages to upgrade; upgrade everything when empty
+        packages: Vec<String>,
+    },
+
     /// Check system health and diagnose issues
-    Doctor,
+    Doctor {
+        /// Attempt to remediate problems that have a suggested fix
+        #[arg(long)]
+        fix: bool,
+    },
+
+    /// Generate a shell completion script on stdout
+    Completions {
+        /// Shell to generate completions for
+        shell: CompletionShell,
+    },
+
+    /// Generate a roff man page on stdout
+    Man,
+}
+
+/// Shells alloy can emit completions for.
+///
+/// This mirrors `clap_complete::Shell` but adds Nushell, whose generator lives
+/// in the separate `clap_complete_nushell` crate.
+#[derive(Copy, Clone, Debug, clap::ValueEnum)]
+enum CompletionShell {
+    Bash,
+    Zsh,
+    Fish,
+    #[value(name = "powershell")]
+    PowerShell,
+    Nushell,
 }
 
 fn main() {
@@ -68,45 +124,413 @@ fn main() {
         Commands::Info { package } => {
             cmd_info(&package);
         }
-        Commands::Doctor => {
-            cmd_doctor();
+        Commands::Search { query } => {
+            cmd_search(&query);
+        }
+        Commands::Upgrade { packages } => {
+            cmd_upgrade(&packages, cli.sudoloop, cli.dry_run);
+        }
+        Commands::Doctor { fix } => {
+            cmd_doctor(fix, cli.dry_run);
+        }
+        Commands::Completions { shell } => {
+            cmd_completions(shell);
         }
+        Commands::Man => {
+            cmd_man();
+        }
+    }
+}
+
+fn cmd_completions(shell: CompletionShell) {
+    let mut cmd = Cli::command();
+    let name = cmd.get_name().to_string();
+    let mut out = std::io::stdout();
+    match shell {
+        CompletionShell::Bash => generate(Shell::Bash, &mut cmd, name, &mut out),
+        CompletionShell::Zsh => generate(Shell::Zsh, &mut cmd, name, &mut out),
+        CompletionShell::Fish => generate(Shell::Fish, &mut cmd, name, &mut out),
+        CompletionShell::PowerShell => generate(Shell::PowerShell, &mut cmd, name, &mut out),
+        CompletionShell::Nushell => {
+            generate(clap_complete_nushell::Nushell, &mut cmd, name, &mut out)
+        }
+    }
+}
+
+fn cmd_man() {
+    let cmd = Cli::command();
+    if let Err(e) = clap_mangen::Man::new(cmd).render(&mut std::io::stdout()) {
+        eprintln!("error: failed to render man page: {}", e);
+        std::process::exit(1);
     }
 }
 
 fn cmd_install(package: &str, version: Option<&str>, dry_run: bool) {
-    match version {
-        Some(v) => println!("Installing {}@{}", package, v),
-        None => println!("Installing {} (latest)", package),
+    let config = match Config::load() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("error: failed to load config: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    // An inline constraint on the package argument (e.g. `foo>=1.2`) takes
+    // precedence; otherwise fall back to the exact `--version` flag.
+    let (name, req) = match version::parse_spec(package) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    // When a constraint is present, pick the highest satisfying version from
+    // the repositories before resolving the dependency graph.
+    let selected: Option<String> = match (&req, version) {
+        (Some(req), _) => {
+            let candidates = config.available_versions(&name);
+            match version::select_version(&candidates, req) {
+                Ok(v) => Some(v),
+                Err(considered) => {
+                    eprintln!(
+                        "error: no version of {} satisfies {}",
+                        name, req
+                    );
+                    if considered.is_empty() {
+                        eprintln!("  (no versions available)");
+                    } else {
+                        eprintln!("  considered: {}", considered.join(", "));
+                    }
+                    std::process::exit(1);
+                }
+            }
+        }
+        (None, explicit) => explicit.map(String::from),
+    };
+
+    // Resolve the full dependency graph and order it dependencies-first.
+    let package = name.as_str();
+    let order = match resolver::resolve_install_order(package, selected.as_deref(), &config) {
+        Ok(order) => order,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    if dry_run {
+        println!("[dry-run] Install plan:");
+        for meta in &order {
+            let role = if meta.name == package {
+                "explicit"
+            } else {
+                "dependency"
+            };
+            println!("  {}@{} (from {}, {})", meta.name, meta.version, meta.repo, role);
+        }
+        return;
+    }
+
+    let mut db = match Database::load() {
+        Ok(db) => db,
+        Err(e) => {
+            eprintln!("error: failed to open installed database: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    for meta in &order {
+        println!("Installing {}@{} (from {})", meta.name, meta.version, meta.repo);
+        let artifact = match config.fetch(meta) {
+            Ok(path) => path,
+            Err(e) => {
+                eprintln!("error: failed to fetch {}: {}", meta.name, e);
+                std::process::exit(1);
+            }
+        };
+        println!("  fetched {}", artifact.display());
+        // No files are placed on the system yet, so the record's file list is
+        // empty.
+        let record = PackageRecord {
+            name: meta.name.clone(),
+            version: meta.version.clone(),
+            installed_at: now(),
+            explicit: meta.name == package,
+            files: Vec::new(),
+        };
+        if let Err(e) = db.record_install(&record) {
+            eprintln!("error: failed to record install: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn cmd_search(query: &str) {
+    let config = match Config::load() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("error: failed to load config: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    // Aggregate hits across every repository. The first repository to advertise
+    // a given name wins, so higher-priority repos shadow lower ones.
+    let mut seen = std::collections::HashSet::new();
+    let mut results: Vec<PackageMeta> = Vec::new();
+    for repo in config.repositories() {
+        for meta in repo.search(query) {
+            if seen.insert(meta.name.clone()) {
+                results.push(meta);
+            }
+        }
+    }
+
+    if results.is_empty() {
+        println!("No packages matching '{}'", query);
+        return;
+    }
+    for meta in results {
+        println!(
+            "{}/{} {}  {}",
+            meta.repo, meta.name, meta.version, meta.description
+        );
+    }
+}
+
+fn cmd_upgrade(packages: &[String], sudoloop: bool, dry_run: bool) {
+    let config = match Config::load() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("error: failed to load config: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let mut db = match Database::load() {
+        Ok(db) => db,
+        Err(e) => {
+            eprintln!("error: failed to open installed database: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    // Decide which installed packages to consider.
+    let installed = db.list();
+    let candidates: Vec<PackageRecord> = if packages.is_empty() {
+        installed
+    } else {
+        let mut chosen = Vec::new();
+        for name in packages {
+            match installed.iter().find(|r| &r.name == name) {
+                Some(record) => chosen.push(record.clone()),
+                None => eprintln!("warning: {} is not installed, skipping", name),
+            }
+        }
+        chosen
+    };
+
+    // Diff each installed version against the newest available version.
+    let mut plan: Vec<(PackageRecord, String)> = Vec::new();
+    for record in candidates {
+        let available = config.available_versions(&record.name);
+        if let Some(newest) = version::highest(&available) {
+            if version::is_newer(&newest, &record.version) {
+                plan.push((record, newest));
+            }
+        }
+    }
+
+    if plan.is_empty() {
+        println!("Nothing to upgrade");
+        return;
+    }
+
+    println!("Upgrade plan:");
+    for (record, newest) in &plan {
+        println!("  {} {} -> {}", record.name, record.version, newest);
     }
+
     if dry_run {
-        println!("[dry-run] Would install {}", package);
+        println!("[dry-run] Would upgrade {} package(s)", plan.len());
+        return;
+    }
+
+    // Installing onto the system needs root, but we refuse to run the whole
+    // process as root; escalate the mutating steps instead.
+    privilege::refuse_root();
+    let _sudoloop = if sudoloop {
+        Some(privilege::SudoLoop::start())
+    } else {
+        None
+    };
+
+    for (record, newest) in plan {
+        println!("Upgrading {} {} -> {}", record.name, record.version, newest);
+        // Only the record is updated; no files are placed and nothing runs
+        // through sudo. The explicit/dependency status and file list carry over.
+        let upgraded = PackageRecord {
+            name: record.name.clone(),
+            version: newest,
+            installed_at: now(),
+            explicit: record.explicit,
+            files: record.files.clone(),
+        };
+        if let Err(e) = db.record_install(&upgraded) {
+            eprintln!("error: failed to record upgrade: {}", e);
+            std::process::exit(1);
+        }
     }
-    // TODO: Implement installation logic
 }
 
 fn cmd_remove(package: &str, dry_run: bool) {
-    println!("Removing {}", package);
+    let mut db = match Database::load() {
+        Ok(db) => db,
+        Err(e) => {
+            eprintln!("error: failed to open installed database: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let record = match db.get(package) {
+        Some(record) => record.clone(),
+        None => {
+            eprintln!("error: {} is not installed", package);
+            std::process::exit(1);
+        }
+    };
+
+    println!("Removing {}@{}", record.name, record.version);
+    for file in &record.files {
+        if dry_run {
+            println!("[dry-run] Would delete {}", file);
+        } else if let Err(e) = std::fs::remove_file(file) {
+            eprintln!("warning: could not delete {}: {}", file, e);
+        }
+    }
+
     if dry_run {
         println!("[dry-run] Would remove {}", package);
+        return;
+    }
+
+    if let Err(e) = db.remove(package) {
+        eprintln!("error: failed to update installed database: {}", e);
+        std::process::exit(1);
     }
-    // TODO: Implement removal logic
 }
 
 fn cmd_list(verbose: bool) {
-    println!("Listing installed packages");
-    if verbose {
-        println!("(verbose mode)");
+    let db = match Database::load() {
+        Ok(db) => db,
+        Err(e) => {
+            eprintln!("error: failed to open installed database: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    for record in db.list() {
+        if verbose {
+            let kind = if record.is_dependency() {
+                "dependency"
+            } else {
+                "explicit"
+            };
+            println!(
+                "{} {}  (installed {}, {})",
+                record.name,
+                record.version,
+                format_timestamp(record.installed_at),
+                kind,
+            );
+        } else {
+            println!("{}", record.name);
+        }
     }
-    // TODO: Implement list logic
 }
 
 fn cmd_info(package: &str) {
+    let db = match Database::load() {
+        Ok(db) => db,
+        Err(e) => {
+            eprintln!("error: failed to open installed database: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let config = match Config::load() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("error: failed to load config: {}", e);
+            std::process::exit(1);
+        }
+    };
+
     println!("Package info: {}", package);
-    // TODO: Implement info logic
+
+    // Registry metadata, merged across the configured repositories.
+    match config.resolve(package, None) {
+        Some(meta) => {
+            println!("repository: {}", meta.repo);
+            println!("latest: {}", meta.version);
+            if !meta.description.is_empty() {
+                println!("description: {}", meta.description);
+            }
+            if !meta.depends.is_empty() {
+                println!("depends: {}", meta.depends.join(", "));
+            }
+            let versions = config.available_versions(package);
+            if !versions.is_empty() {
+                println!("available: {}", versions.join(", "));
+            }
+        }
+        None => println!("not available in any configured repository"),
+    }
+
+    // Local install record.
+    match db.get(package) {
+        Some(record) => println!("installed: yes (version {})", record.version),
+        None => println!("installed: no"),
+    }
+}
+
+/// Current wall-clock time as whole seconds since the Unix epoch.
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Renders a Unix timestamp as an ISO-8601 UTC date-time without pulling in a
+/// date library.
+fn format_timestamp(secs: u64) -> String {
+    // Days since the epoch, and seconds within the final day.
+    let days = secs / 86_400;
+    let rem = secs % 86_400;
+    let (hour, minute, second) = (rem / 3600, (rem % 3600) / 60, rem % 60);
+
+    // Convert day count to a civil date (algorithm by Howard Hinnant).
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let year = yoe + era * 400;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = if month <= 2 { year + 1 } else { year };
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year, month, day, hour, minute, second
+    )
 }
 
-fn cmd_doctor() {
+fn cmd_doctor(fix: bool, dry_run: bool) {
     println!("Running system health check...");
-    // TODO: Implement doctor logic
+    let code = doctor::run(fix, dry_run);
+    if code != 0 {
+        std::process::exit(code);
+    }
 }
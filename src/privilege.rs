@@ -0,0 +1,66 @@
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+/// How often the sudoloop refreshes the cached sudo credential.
+const SUDO_REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Returns `true` if alloy is running as root.
+pub fn is_root() -> bool {
+    // SAFETY: `geteuid` is always safe to call and never fails.
+    unsafe { libc::geteuid() == 0 }
+}
+
+/// Aborts when invoked as root.
+///
+/// `upgrade` calls this before it changes anything; the other commands do not
+/// check. No step is run through `sudo` apart from the credential refresh done
+/// by [`SudoLoop`].
+pub fn refuse_root() {
+    if is_root() {
+        eprintln!("error: refusing to run as root; run as a normal user and alloy will escalate individual steps via sudo");
+        std::process::exit(1);
+    }
+}
+
+/// A background task that keeps the sudo timestamp fresh so long multi-package
+/// operations don't stall on re-prompts. Stops when dropped.
+pub struct SudoLoop {
+    stop: Arc<AtomicBool>,
+    handle: Option<thread::JoinHandle<()>>,
+}
+
+impl SudoLoop {
+    /// Primes the sudo credential and spawns the refresh loop.
+    pub fn start() -> Self {
+        // Prime the credential up front so any prompt happens now, not mid-run.
+        let _ = Command::new("sudo").arg("-v").status();
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = Arc::clone(&stop);
+        let handle = thread::spawn(move || {
+            while !thread_stop.load(Ordering::Relaxed) {
+                thread::sleep(SUDO_REFRESH_INTERVAL);
+                if thread_stop.load(Ordering::Relaxed) {
+                    break;
+                }
+                let _ = Command::new("sudo").arg("-v").status();
+            }
+        });
+        Self {
+            stop,
+            handle: Some(handle),
+        }
+    }
+}
+
+impl Drop for SudoLoop {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
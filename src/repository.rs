@@ -0,0 +1,468 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+/// Metadata describing a single package as advertised by a repository.
+#[derive(Debug, Clone)]
+pub struct PackageMeta {
+    /// Package name.
+    pub name: String,
+    /// Version offered by the repository.
+    pub version: String,
+    /// Short human-readable description.
+    pub description: String,
+    /// Names of packages this one depends on.
+    pub depends: Vec<String>,
+    /// Name of the repository that advertised this package.
+    pub repo: String,
+}
+
+/// A source alloy can query for packages.
+///
+/// Implementors stand in for anything from a remote HTTP index to a local
+/// directory; `cmd_install` and `cmd_search` only ever talk to this trait so a
+/// new backend can be dropped in without touching the commands.
+pub trait Repository {
+    /// Human-readable name of the repository, e.g. `core`.
+    fn name(&self) -> &str;
+
+    /// Returns every package whose name matches `query`.
+    fn search(&self, query: &str) -> Vec<PackageMeta>;
+
+    /// Resolves a package by name and optional version, returning its metadata
+    /// if the repository carries it.
+    fn resolve(&self, name: &str, version: Option<&str>) -> Option<PackageMeta>;
+
+    /// Downloads the package described by `meta` and returns the local path of
+    /// the fetched artifact.
+    fn fetch(&self, meta: &PackageMeta) -> io::Result<PathBuf>;
+
+    /// Returns every version of `name` the repository offers, used to satisfy
+    /// inline version constraints.
+    fn available_versions(&self, _name: &str) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+/// A repository served over HTTP, configured from `config.toml`.
+pub struct HttpRepository {
+    name: String,
+    url: String,
+}
+
+impl Repository for HttpRepository {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn search(&self, _query: &str) -> Vec<PackageMeta> {
+        // The HTTP index client is not implemented yet; warn rather than
+        // returning an empty result that reads as "searched and found nothing".
+        eprintln!(
+            "warning: repository '{}' ({}) uses the HTTP backend, which is not implemented yet; skipping",
+            self.name, self.url
+        );
+        Vec::new()
+    }
+
+    fn resolve(&self, _name: &str, _version: Option<&str>) -> Option<PackageMeta> {
+        eprintln!(
+            "warning: repository '{}' ({}) uses the HTTP backend, which is not implemented yet; skipping",
+            self.name, self.url
+        );
+        None
+    }
+
+    fn fetch(&self, _meta: &PackageMeta) -> io::Result<PathBuf> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            format!("remote fetch from {} is not implemented yet", self.url),
+        ))
+    }
+
+    fn available_versions(&self, _name: &str) -> Vec<String> {
+        eprintln!(
+            "warning: repository '{}' ({}) uses the HTTP backend, which is not implemented yet; skipping",
+            self.name, self.url
+        );
+        Vec::new()
+    }
+}
+
+/// A repository backed by a local directory of package manifests.
+///
+/// Each `*.toml` file in the directory describes one package version with the
+/// same fields as a registry entry (`name`, `version`, `description`,
+/// `depends`). This is the working production backend: it makes `install`,
+/// `search`, and `upgrade` functional against a filesystem mirror while the
+/// HTTP backend is still a stub.
+pub struct LocalRepository {
+    name: String,
+    /// Each advertised package paired with the manifest file it was read from.
+    packages: Vec<(PackageMeta, PathBuf)>,
+}
+
+/// On-disk shape of a single package manifest in a local repository directory.
+#[derive(Debug, Deserialize)]
+struct LocalManifest {
+    name: String,
+    version: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    depends: Vec<String>,
+}
+
+impl LocalRepository {
+    /// Loads every `*.toml` package manifest found directly in `dir`.
+    fn load(name: String, dir: &Path) -> io::Result<Self> {
+        let mut packages = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("toml") {
+                continue;
+            }
+            let text = fs::read_to_string(&path)?;
+            let manifest: LocalManifest = toml::from_str(&text)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            let meta = PackageMeta {
+                name: manifest.name,
+                version: manifest.version,
+                description: manifest.description,
+                depends: manifest.depends,
+                repo: name.clone(),
+            };
+            packages.push((meta, path));
+        }
+        Ok(Self { name, packages })
+    }
+}
+
+impl Repository for LocalRepository {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn search(&self, query: &str) -> Vec<PackageMeta> {
+        self.packages
+            .iter()
+            .filter(|(meta, _)| meta.name.contains(query))
+            .map(|(meta, _)| meta.clone())
+            .collect()
+    }
+
+    fn resolve(&self, name: &str, version: Option<&str>) -> Option<PackageMeta> {
+        let mut matches: Vec<&PackageMeta> = self
+            .packages
+            .iter()
+            .map(|(meta, _)| meta)
+            .filter(|meta| meta.name == name)
+            .collect();
+        match version {
+            Some(version) => matches
+                .into_iter()
+                .find(|meta| meta.version == version)
+                .cloned(),
+            None => {
+                // Newest version wins when no exact version is requested.
+                matches.sort_by_key(|meta| crate::version::parse_lenient(&meta.version));
+                matches.last().map(|meta| (*meta).clone())
+            }
+        }
+    }
+
+    fn fetch(&self, meta: &PackageMeta) -> io::Result<PathBuf> {
+        self.packages
+            .iter()
+            .find(|(m, _)| m.name == meta.name && m.version == meta.version)
+            .map(|(_, path)| path.clone())
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("{}@{} not present in {}", meta.name, meta.version, self.name),
+                )
+            })
+    }
+
+    fn available_versions(&self, name: &str) -> Vec<String> {
+        self.packages
+            .iter()
+            .filter(|(meta, _)| meta.name == name)
+            .map(|(meta, _)| meta.version.clone())
+            .collect()
+    }
+}
+
+/// An in-memory repository backed by a fixed set of package metadata.
+///
+/// Test-only backend used by the repository and resolver tests so they run
+/// without a live remote index.
+#[cfg(test)]
+pub struct MemoryRepository {
+    name: String,
+    packages: Vec<PackageMeta>,
+}
+
+#[cfg(test)]
+impl MemoryRepository {
+    /// Builds a repository serving `packages`, stamping each with `name` as its
+    /// origin repository.
+    pub fn new(name: impl Into<String>, packages: Vec<PackageMeta>) -> Self {
+        let name = name.into();
+        let packages = packages
+            .into_iter()
+            .map(|mut meta| {
+                meta.repo = name.clone();
+                meta
+            })
+            .collect();
+        Self { name, packages }
+    }
+}
+
+#[cfg(test)]
+impl Repository for MemoryRepository {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn search(&self, query: &str) -> Vec<PackageMeta> {
+        self.packages
+            .iter()
+            .filter(|meta| meta.name.contains(query))
+            .cloned()
+            .collect()
+    }
+
+    fn resolve(&self, name: &str, version: Option<&str>) -> Option<PackageMeta> {
+        let mut matches: Vec<&PackageMeta> =
+            self.packages.iter().filter(|meta| meta.name == name).collect();
+        match version {
+            Some(version) => matches
+                .into_iter()
+                .find(|meta| meta.version == version)
+                .cloned(),
+            None => {
+                // Newest version wins when no exact version is requested.
+                matches.sort_by_key(|meta| crate::version::parse_lenient(&meta.version));
+                matches.last().map(|meta| (*meta).clone())
+            }
+        }
+    }
+
+    fn fetch(&self, meta: &PackageMeta) -> io::Result<PathBuf> {
+        Ok(std::env::temp_dir().join(format!("{}-{}", meta.name, meta.version)))
+    }
+
+    fn available_versions(&self, name: &str) -> Vec<String> {
+        self.packages
+            .iter()
+            .filter(|meta| meta.name == name)
+            .map(|meta| meta.version.clone())
+            .collect()
+    }
+}
+
+/// On-disk shape of a single `[[repository]]` entry in `config.toml`.
+///
+/// At least one of `url` (HTTP backend) or `path` (local-directory backend)
+/// must be set; when both are, `path` is used and `url` is ignored.
+#[derive(Debug, Deserialize)]
+struct RepositoryEntry {
+    /// Repository identifier in the `name:index` form, e.g. `core:0`.
+    name: String,
+    #[serde(default)]
+    url: Option<String>,
+    #[serde(default)]
+    path: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ConfigFile {
+    #[serde(default, rename = "repository")]
+    repositories: Vec<RepositoryEntry>,
+}
+
+/// The ordered set of repositories alloy consults, built from `config.toml`.
+pub struct Config {
+    repositories: Vec<Box<dyn Repository>>,
+}
+
+impl Config {
+    /// Default config path, `/etc/alloy/config.toml`.
+    pub fn default_path() -> PathBuf {
+        PathBuf::from("/etc/alloy/config.toml")
+    }
+
+    /// Loads the config from the default path, returning an empty config when
+    /// the file is absent.
+    pub fn load() -> io::Result<Self> {
+        Self::load_from(Self::default_path())
+    }
+
+    /// Loads the config from an explicit path.
+    pub fn load_from(path: PathBuf) -> io::Result<Self> {
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                return Ok(Self {
+                    repositories: Vec::new(),
+                });
+            }
+            Err(e) => return Err(e),
+        };
+        let parsed: ConfigFile =
+            toml::from_str(&text).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        let mut repositories: Vec<(u32, Box<dyn Repository>)> =
+            Vec::with_capacity(parsed.repositories.len());
+        for entry in parsed.repositories {
+            let (name, priority) = parse_name_index(&entry.name)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            let repo: Box<dyn Repository> = match (entry.path, entry.url) {
+                (Some(path), _) => Box::new(LocalRepository::load(name, Path::new(&path))?),
+                (None, Some(url)) => Box::new(HttpRepository { name, url }),
+                (None, None) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("repository '{}' has neither a url nor a path", name),
+                    ));
+                }
+            };
+            repositories.push((priority, repo));
+        }
+        // Consult repositories in ascending priority order.
+        repositories.sort_by_key(|(priority, _)| *priority);
+        Ok(Self {
+            repositories: repositories.into_iter().map(|(_, repo)| repo).collect(),
+        })
+    }
+
+    /// Builds a config from an explicit, already-ordered set of repositories.
+    #[cfg(test)]
+    pub fn from_repositories(repositories: Vec<Box<dyn Repository>>) -> Self {
+        Self { repositories }
+    }
+
+    /// The configured repositories, already ordered by priority.
+    pub fn repositories(&self) -> &[Box<dyn Repository>] {
+        &self.repositories
+    }
+
+    /// Resolves a package by walking repositories in priority order and taking
+    /// the first that carries it.
+    pub fn resolve(&self, name: &str, version: Option<&str>) -> Option<PackageMeta> {
+        self.repositories
+            .iter()
+            .find_map(|repo| repo.resolve(name, version))
+    }
+
+    /// Gathers every version of `name` advertised across all repositories.
+    pub fn available_versions(&self, name: &str) -> Vec<String> {
+        self.repositories
+            .iter()
+            .flat_map(|repo| repo.available_versions(name))
+            .collect()
+    }
+
+    /// Downloads `meta` from the repository that advertised it.
+    pub fn fetch(&self, meta: &PackageMeta) -> io::Result<PathBuf> {
+        match self.repositories.iter().find(|repo| repo.name() == meta.repo) {
+            Some(repo) => repo.fetch(meta),
+            None => Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("no repository named {}", meta.repo),
+            )),
+        }
+    }
+}
+
+/// Splits a `name:index` identifier into its name and numeric priority.
+pub(crate) fn parse_name_index(raw: &str) -> Result<(String, u32), String> {
+    match raw.rsplit_once(':') {
+        Some((name, index)) => {
+            let priority = index
+                .parse::<u32>()
+                .map_err(|_| format!("invalid repository priority in '{}'", raw))?;
+            Ok((name.to_string(), priority))
+        }
+        None => Err(format!("repository '{}' is missing a ':index' priority", raw)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_name_index() {
+        assert_eq!(parse_name_index("core:0").unwrap(), ("core".to_string(), 0));
+        assert_eq!(parse_name_index("extra:10").unwrap(), ("extra".to_string(), 10));
+    }
+
+    #[test]
+    fn rejects_missing_or_invalid_priority() {
+        assert!(parse_name_index("core").is_err());
+        assert!(parse_name_index("core:notanumber").is_err());
+    }
+
+    #[test]
+    fn memory_repository_resolves_and_searches() {
+        let repo = MemoryRepository::new(
+            "local",
+            vec![
+                PackageMeta {
+                    name: "foo".to_string(),
+                    version: "1.0.0".to_string(),
+                    description: "the foo tool".to_string(),
+                    depends: vec![],
+                    repo: String::new(),
+                },
+                PackageMeta {
+                    name: "foo".to_string(),
+                    version: "2.0.0".to_string(),
+                    description: "the foo tool".to_string(),
+                    depends: vec![],
+                    repo: String::new(),
+                },
+            ],
+        );
+        // No version requested resolves to the newest.
+        assert_eq!(repo.resolve("foo", None).unwrap().version, "2.0.0");
+        assert_eq!(repo.resolve("foo", Some("1.0.0")).unwrap().version, "1.0.0");
+        assert!(repo.resolve("bar", None).is_none());
+        assert_eq!(repo.search("fo").len(), 2);
+        // Metadata is stamped with the origin repository name.
+        assert_eq!(repo.resolve("foo", None).unwrap().repo, "local");
+    }
+
+    #[test]
+    fn local_repository_reads_manifests() {
+        let dir = std::env::temp_dir().join(format!("alloy-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("foo-1.0.0.toml"),
+            "name = \"foo\"\nversion = \"1.0.0\"\ndescription = \"the foo tool\"\ndepends = [\"bar\"]\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("foo-1.2.0.toml"),
+            "name = \"foo\"\nversion = \"1.2.0\"\n",
+        )
+        .unwrap();
+
+        let repo = LocalRepository::load("local".to_string(), &dir).unwrap();
+        // Newest version wins, and depends survive the round-trip.
+        let newest = repo.resolve("foo", None).unwrap();
+        assert_eq!(newest.version, "1.2.0");
+        assert_eq!(repo.available_versions("foo").len(), 2);
+        let pinned = repo.resolve("foo", Some("1.0.0")).unwrap();
+        assert_eq!(pinned.depends, vec!["bar".to_string()]);
+        // fetch returns the manifest path it was loaded from.
+        assert!(repo.fetch(&pinned).unwrap().ends_with("foo-1.0.0.toml"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,159 @@
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+
+use crate::repository::{Config, PackageMeta};
+
+/// Reasons dependency resolution can fail.
+#[derive(Debug)]
+pub enum ResolveError {
+    /// A required package could not be found in any repository.
+    Missing(String),
+    /// The dependency graph contains a cycle; carries the packages still
+    /// carrying a nonzero in-degree when resolution stalled.
+    Cycle(Vec<String>),
+}
+
+impl fmt::Display for ResolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResolveError::Missing(name) => {
+                write!(f, "package {} not found in any repository", name)
+            }
+            ResolveError::Cycle(nodes) => {
+                write!(f, "dependency cycle among: {}", nodes.join(", "))
+            }
+        }
+    }
+}
+
+impl std::error::Error for ResolveError {}
+
+/// Builds the dependency graph rooted at `root` and returns a correct install
+/// order — dependencies before the packages that need them — using Kahn's
+/// algorithm.
+pub fn resolve_install_order(
+    root: &str,
+    version: Option<&str>,
+    config: &Config,
+) -> Result<Vec<PackageMeta>, ResolveError> {
+    // Collect every transitively-reachable package, resolving each exactly once.
+    let mut metas: HashMap<String, PackageMeta> = HashMap::new();
+    let mut queue = VecDeque::new();
+    queue.push_back((root.to_string(), version.map(String::from)));
+    while let Some((name, req)) = queue.pop_front() {
+        if metas.contains_key(&name) {
+            continue;
+        }
+        let meta = config
+            .resolve(&name, req.as_deref())
+            .ok_or_else(|| ResolveError::Missing(name.clone()))?;
+        for dep in &meta.depends {
+            if !metas.contains_key(dep) {
+                queue.push_back((dep.clone(), None));
+            }
+        }
+        metas.insert(name, meta);
+    }
+
+    // in_degree[p] = number of p's own dependencies (edges dep -> p), and
+    // dependents[d] lists the packages that depend on d.
+    let mut in_degree: HashMap<String, usize> = HashMap::new();
+    let mut dependents: HashMap<String, Vec<String>> = HashMap::new();
+    for meta in metas.values() {
+        in_degree.entry(meta.name.clone()).or_insert(0);
+        for dep in &meta.depends {
+            *in_degree.entry(meta.name.clone()).or_insert(0) += 1;
+            dependents
+                .entry(dep.clone())
+                .or_default()
+                .push(meta.name.clone());
+        }
+    }
+
+    // Seed the queue with every zero-in-degree node (no outstanding deps).
+    let mut ready: VecDeque<String> = in_degree
+        .iter()
+        .filter(|(_, &deg)| deg == 0)
+        .map(|(name, _)| name.clone())
+        .collect();
+
+    let mut order = Vec::with_capacity(metas.len());
+    while let Some(name) = ready.pop_front() {
+        order.push(metas[&name].clone());
+        if let Some(children) = dependents.get(&name) {
+            for child in children {
+                let deg = in_degree.get_mut(child).expect("dependent has in-degree");
+                *deg -= 1;
+                if *deg == 0 {
+                    ready.push_back(child.clone());
+                }
+            }
+        }
+    }
+
+    // Anything left with a nonzero in-degree is in a cycle or depends on one.
+    if order.len() < metas.len() {
+        let mut cycle: Vec<String> = in_degree
+            .into_iter()
+            .filter(|(_, deg)| *deg > 0)
+            .map(|(name, _)| name)
+            .collect();
+        cycle.sort();
+        return Err(ResolveError::Cycle(cycle));
+    }
+
+    Ok(order)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repository::MemoryRepository;
+
+    fn meta(name: &str, depends: &[&str]) -> PackageMeta {
+        PackageMeta {
+            name: name.to_string(),
+            version: "1.0.0".to_string(),
+            description: String::new(),
+            depends: depends.iter().map(|d| d.to_string()).collect(),
+            repo: String::new(),
+        }
+    }
+
+    fn config(packages: Vec<PackageMeta>) -> Config {
+        Config::from_repositories(vec![Box::new(MemoryRepository::new("local", packages))])
+    }
+
+    #[test]
+    fn orders_dependencies_before_dependents() {
+        // app -> lib -> core
+        let cfg = config(vec![
+            meta("app", &["lib"]),
+            meta("lib", &["core"]),
+            meta("core", &[]),
+        ]);
+        let order = resolve_install_order("app", None, &cfg).unwrap();
+        let names: Vec<&str> = order.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, vec!["core", "lib", "app"]);
+    }
+
+    #[test]
+    fn detects_cycles() {
+        let cfg = config(vec![meta("a", &["b"]), meta("b", &["a"])]);
+        match resolve_install_order("a", None, &cfg) {
+            Err(ResolveError::Cycle(nodes)) => {
+                assert_eq!(nodes, vec!["a".to_string(), "b".to_string()]);
+            }
+            other => panic!("expected cycle, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn reports_missing_dependency() {
+        let cfg = config(vec![meta("app", &["missing"])]);
+        match resolve_install_order("app", None, &cfg) {
+            Err(ResolveError::Missing(name)) => assert_eq!(name, "missing"),
+            other => panic!("expected missing, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,211 @@
+use std::fmt;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use semver::Version;
+
+/// Comparison operators accepted in an install spec.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Ge,
+    Le,
+    Gt,
+    Lt,
+    Eq,
+}
+
+impl fmt::Display for Op {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Op::Ge => ">=",
+            Op::Le => "<=",
+            Op::Gt => ">",
+            Op::Lt => "<",
+            Op::Eq => "=",
+        };
+        f.write_str(s)
+    }
+}
+
+/// A single version constraint such as `>=1.2`.
+#[derive(Debug, Clone)]
+pub struct VersionReq {
+    pub op: Op,
+    pub version: Version,
+}
+
+impl VersionReq {
+    /// Returns `true` if `candidate` satisfies this constraint.
+    pub fn matches(&self, candidate: &Version) -> bool {
+        match self.op {
+            Op::Ge => candidate >= &self.version,
+            Op::Le => candidate <= &self.version,
+            Op::Gt => candidate > &self.version,
+            Op::Lt => candidate < &self.version,
+            Op::Eq => candidate == &self.version,
+        }
+    }
+}
+
+impl fmt::Display for VersionReq {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.op, self.version)
+    }
+}
+
+// Captures `name` and an optional `op + version` suffix, matching the inline
+// constraint syntax AUR helpers accept (`foo>=1.2`, `foo<3`, `foo=1.0`).
+static SPEC_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^(?P<name>[^<>=]+)(?P<req>(?:>=|<=|>|<|=)(?P<version>.+))?$").unwrap());
+
+/// Parses a possibly-partial version like `1.2` or `3` by padding missing
+/// components with zeros, since `semver::Version` requires a full
+/// `MAJOR.MINOR.PATCH`.
+pub fn parse_lenient(raw: &str) -> Option<Version> {
+    // Split off any pre-release / build metadata before padding the core.
+    let (core, suffix) = match raw.find(['-', '+']) {
+        Some(idx) => (&raw[..idx], &raw[idx..]),
+        None => (raw, ""),
+    };
+    let mut parts: Vec<&str> = core.split('.').collect();
+    if parts.is_empty() || parts.len() > 3 {
+        return None;
+    }
+    while parts.len() < 3 {
+        parts.push("0");
+    }
+    Version::parse(&format!("{}{}", parts.join("."), suffix)).ok()
+}
+
+/// Parses an install spec into its package name and optional version
+/// constraint.
+pub fn parse_spec(spec: &str) -> Result<(String, Option<VersionReq>), String> {
+    let caps = SPEC_RE
+        .captures(spec)
+        .ok_or_else(|| format!("invalid package spec '{}'", spec))?;
+    let name = caps["name"].to_string();
+
+    let req = match caps.name("req") {
+        None => None,
+        Some(_) => {
+            let op = if spec[name.len()..].starts_with(">=") {
+                Op::Ge
+            } else if spec[name.len()..].starts_with("<=") {
+                Op::Le
+            } else if spec[name.len()..].starts_with('>') {
+                Op::Gt
+            } else if spec[name.len()..].starts_with('<') {
+                Op::Lt
+            } else {
+                Op::Eq
+            };
+            let raw = &caps["version"];
+            let version = parse_lenient(raw)
+                .ok_or_else(|| format!("invalid version '{}' in spec", raw))?;
+            Some(VersionReq { op, version })
+        }
+    };
+    Ok((name, req))
+}
+
+/// Returns the highest parseable version in `available`, if any.
+pub fn highest(available: &[String]) -> Option<String> {
+    available
+        .iter()
+        .filter_map(|raw| parse_lenient(raw).map(|v| (v, raw)))
+        .max_by(|a, b| a.0.cmp(&b.0))
+        .map(|(_, raw)| raw.clone())
+}
+
+/// Returns `true` if `newer` is a strictly greater semver than `older`; when
+/// either side does not parse, returns `true` if the two strings differ.
+pub fn is_newer(newer: &str, older: &str) -> bool {
+    match (parse_lenient(newer), parse_lenient(older)) {
+        (Some(n), Some(o)) => n > o,
+        _ => newer != older,
+    }
+}
+
+/// Picks the highest version from `available` that satisfies `req`.
+///
+/// Returns `Err` with the candidate versions that were considered when none
+/// match, so the caller can explain the rejection.
+pub fn select_version(
+    available: &[String],
+    req: &VersionReq,
+) -> Result<String, Vec<String>> {
+    let mut best: Option<(Version, &String)> = None;
+    for raw in available {
+        let Some(version) = parse_lenient(raw) else {
+            continue;
+        };
+        if req.matches(&version) {
+            match &best {
+                Some((current, _)) if current >= &version => {}
+                _ => best = Some((version, raw)),
+            }
+        }
+    }
+    match best {
+        Some((_, raw)) => Ok(raw.clone()),
+        None => Err(available.to_vec()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_partial_constraints() {
+        let (name, req) = parse_spec("foo>=1.2").unwrap();
+        let req = req.unwrap();
+        assert_eq!(name, "foo");
+        assert_eq!(req.op, Op::Ge);
+        assert_eq!(req.version, Version::new(1, 2, 0));
+
+        let (_, req) = parse_spec("foo<3").unwrap();
+        let req = req.unwrap();
+        assert_eq!(req.op, Op::Lt);
+        assert_eq!(req.version, Version::new(3, 0, 0));
+
+        let (_, req) = parse_spec("foo=1.0").unwrap();
+        let req = req.unwrap();
+        assert_eq!(req.op, Op::Eq);
+        assert_eq!(req.version, Version::new(1, 0, 0));
+    }
+
+    #[test]
+    fn bare_name_has_no_constraint() {
+        let (name, req) = parse_spec("foo").unwrap();
+        assert_eq!(name, "foo");
+        assert!(req.is_none());
+    }
+
+    #[test]
+    fn selects_highest_satisfying_version() {
+        let available = vec![
+            "1.0.0".to_string(),
+            "1.5.0".to_string(),
+            "2.0.0".to_string(),
+        ];
+        let (_, req) = parse_spec("foo<2").unwrap();
+        assert_eq!(select_version(&available, &req.unwrap()).unwrap(), "1.5.0");
+    }
+
+    #[test]
+    fn reports_candidates_when_none_match() {
+        let available = vec!["1.0.0".to_string(), "1.5.0".to_string()];
+        let (_, req) = parse_spec("foo>=2").unwrap();
+        let err = select_version(&available, &req.unwrap()).unwrap_err();
+        assert_eq!(err, available);
+    }
+
+    #[test]
+    fn highest_and_is_newer() {
+        let available = vec!["1.2".to_string(), "1.10".to_string(), "1.3".to_string()];
+        assert_eq!(highest(&available).unwrap(), "1.10");
+        assert!(is_newer("1.10", "1.3"));
+        assert!(!is_newer("1.0", "1.0"));
+    }
+}